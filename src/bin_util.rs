@@ -0,0 +1,61 @@
+//! Checked byte-slice accessors used to pull fields out of a NITF buffer.
+//!
+//! Every field in a NITF header/subheader is a fixed-width run of ASCII (BCS)
+//! bytes at a known offset. These helpers bounds-check before slicing and
+//! parse the common "right-padded with spaces" numeric encoding, so callers
+//! never need to `unwrap()` their way through a short or malformed file.
+
+use std::str;
+
+use error::NitfError;
+
+/// Returns the `len` bytes of `buf` starting at `offset`, or
+/// `NitfError::NotEnoughData` if they aren't all present.
+pub(crate) fn c_field<'a>(buf: &'a [u8], offset: usize, len: usize) -> Result<&'a [u8], NitfError> {
+  buf.get(offset..offset + len)
+    .ok_or(NitfError::NotEnoughData { field: "", offset })
+}
+
+/// Strips the trailing BCS space padding (`0x20`) a field is right-padded with.
+pub(crate) fn trim_bcs_spaces(field: &[u8]) -> &[u8] {
+  let mut end = field.len();
+  while end > 0 && field[end - 1] == b' ' {
+    end -= 1;
+  }
+  &field[..end]
+}
+
+/// Parses a space-trimmed ASCII field as an unsigned integer.
+pub(crate) fn parse_ascii_uint(field: &[u8]) -> Result<u64, NitfError> {
+  let trimmed = trim_bcs_spaces(field);
+  let text = str::from_utf8(trimmed).map_err(|_| NitfError::BadUtf8)?;
+  if text.is_empty() {
+    return Err(NitfError::BadNumber);
+  }
+  text.parse::<u64>().map_err(|_| NitfError::BadNumber)
+}
+
+/// Reads `len` bytes at `offset` and parses them as a space-trimmed ASCII
+/// unsigned integer (e.g. `NUMI`, `LISHn`, `LIn`).
+pub(crate) fn c_uint_ascii(buf: &[u8], offset: usize, len: usize) -> Result<u64, NitfError> {
+  let field = c_field(buf, offset, len)?;
+  parse_ascii_uint(field)
+}
+
+/// Trims the BCS space padding off `field` and validates it as UTF-8,
+/// without parsing it any further.
+pub(crate) fn trimmed_str(field: &[u8]) -> Result<&str, NitfError> {
+  let trimmed = trim_bcs_spaces(field);
+  str::from_utf8(trimmed).map_err(|_| NitfError::BadUtf8)
+}
+
+/// Like `trimmed_str`, but a field that is entirely space padding (the BCS
+/// encoding for "not present") maps to `None` instead of `Some("")`.
+pub(crate) fn optional_trimmed_str(field: &[u8]) -> Result<Option<&str>, NitfError> {
+  let text = trimmed_str(field)?;
+  if text.is_empty() {
+    Ok(None)
+  } else {
+    Ok(Some(text))
+  }
+}
@@ -0,0 +1,40 @@
+//! Error type returned by the NITF parsing routines.
+//!
+//! Parsing a NITF file is bounds- and format-sensitive: a truncated file or a
+//! field that doesn't hold the ASCII digits it claims to should produce a
+//! `Result`, not take down the process.
+
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum NitfError {
+  /// `buf` did not have `len` bytes available at `offset` for `field`.
+  NotEnoughData { field: &'static str, offset: usize },
+  /// A numeric field did not contain a valid unsigned integer.
+  BadNumber,
+  /// A field expected to be ASCII/BCS text was not valid UTF-8.
+  BadUtf8,
+  /// The data exercises something this parser doesn't handle (e.g. an
+  /// unimplemented compression code or bit depth). Image decoding also
+  /// returns it when an image data field is too short for, or inconsistent
+  /// with, its subheader.
+  Unsupported,
+  /// A coded field (e.g. `FSCLASS`) held a byte sequence that isn't one of
+  /// its defined codes.
+  InvalidCode { field: &'static str },
+}
+
+impl fmt::Display for NitfError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      NitfError::NotEnoughData { field, offset } =>
+        write!(f, "not enough data for field {} at offset {}", field, offset),
+      NitfError::BadNumber => write!(f, "field did not contain a valid number"),
+      NitfError::BadUtf8 => write!(f, "field was not valid utf-8"),
+      NitfError::Unsupported => write!(f, "unsupported NITF feature"),
+      NitfError::InvalidCode { field } => write!(f, "field {} held an unrecognized code", field),
+    }
+  }
+}
+
+impl ::std::error::Error for NitfError {}
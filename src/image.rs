@@ -0,0 +1,626 @@
+//! Image subheader parsing and uncompressed pixel decoding.
+//!
+//! An image segment's subheader carries the blocking geometry (`NPPBH`,
+//! `NPPBV`, `NBPR`, `NBPC`) and band layout (`NBANDS`, `IMODE`) needed to
+//! turn the segment's data field into a flat `w`x`h` raster. This module
+//! only reads the subheader fields decoding actually needs; it does not
+//! model the full MIL-STD-2500C image subheader.
+
+use bin_util::{c_field, c_uint_ascii};
+use error::NitfError;
+
+const IM_SIZE: usize = 2;
+const IID1_SIZE: usize = 10;
+const IDATIM_SIZE: usize = 14;
+const TGTID_SIZE: usize = 17;
+const IID2_SIZE: usize = 80;
+const ISCLAS_SIZE: usize = 1;
+const ISCLSY_SIZE: usize = 2;
+const ISCODE_SIZE: usize = 11;
+const ISCTLH_SIZE: usize = 2;
+const ISREL_SIZE: usize = 20;
+const ISDCTP_SIZE: usize = 2;
+const ISDCDT_SIZE: usize = 8;
+const ISDCXM_SIZE: usize = 4;
+const ISDG_SIZE: usize = 1;
+const ISDGDT_SIZE: usize = 8;
+const ISCLTX_SIZE: usize = 43;
+const ISCATP_SIZE: usize = 1;
+const ISCAUT_SIZE: usize = 40;
+const ISCRSN_SIZE: usize = 1;
+const ISSRDT_SIZE: usize = 8;
+const ISCTLN_SIZE: usize = 15;
+const ENCRYP_SIZE: usize = 1;
+const ISORCE_SIZE: usize = 42;
+const NROWS_SIZE: usize = 8;
+const NCOLS_SIZE: usize = 8;
+const PVTYPE_SIZE: usize = 3;
+const IREP_SIZE: usize = 8;
+const ICAT_SIZE: usize = 8;
+const ABPP_SIZE: usize = 2;
+const PJUST_SIZE: usize = 1;
+const ICORDS_SIZE: usize = 1;
+const IGEOLO_SIZE: usize = 60;
+const NICOM_SIZE: usize = 1;
+const ICOM_SIZE: usize = 80;
+const IC_SIZE: usize = 2;
+const COMRAT_SIZE: usize = 4;
+const NBANDS_SIZE: usize = 1;
+const XBANDS_SIZE: usize = 5;
+const IREPBAND_SIZE: usize = 2;
+const ISUBCAT_SIZE: usize = 6;
+const IFC_SIZE: usize = 1;
+const IMFLT_SIZE: usize = 3;
+const NLUTS_SIZE: usize = 1;
+const NELUT_SIZE: usize = 5;
+const ISYNC_SIZE: usize = 1;
+const IMODE_SIZE: usize = 1;
+const NBPR_SIZE: usize = 4;
+const NBPC_SIZE: usize = 4;
+const NPPBH_SIZE: usize = 4;
+const NPPBV_SIZE: usize = 4;
+const NBPP_SIZE: usize = 2;
+
+/// The subset of an image subheader needed to decode pixels.
+#[derive(Debug, Clone, Copy)]
+pub struct ImageSubheader {
+  pub nrows: u32,
+  pub ncols: u32,
+  pub nbands: u32,
+  pub nbpp: u32,
+  pub nbpr: u32,
+  pub nbpc: u32,
+  pub nppbh: u32,
+  pub nppbv: u32,
+  pub imode: u8,
+  pub ic: [u8; 2],
+}
+
+impl ImageSubheader {
+  /// `NPPBH`/`NPPBV`, resolving the MIL-STD-2500C `0000` encoding (legal
+  /// for single-block images whose dimension exceeds 8192) to the image's
+  /// actual width/height.
+  fn block_dims(&self) -> (usize, usize) {
+    let block_w = if self.nppbh == 0 { self.ncols } else { self.nppbh };
+    let block_h = if self.nppbv == 0 { self.nrows } else { self.nppbv };
+    (block_w as usize, block_h as usize)
+  }
+}
+
+/// A single pixel, one 8-bit sample per band.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pixel {
+  pub bands: Vec<u8>,
+}
+
+/// A decoded raster, row-major, `w * h` pixels.
+#[derive(Debug, Clone)]
+pub struct Image {
+  pub w: usize,
+  pub h: usize,
+  pub pixels: Vec<Pixel>,
+}
+
+macro_rules! take {
+  ($buf:expr, $offset:expr, $size:expr, $name:expr) => {{
+    let start = $offset;
+    let field = c_field($buf, start, $size)
+      .map_err(|_| NitfError::NotEnoughData { field: $name, offset: start })?;
+    $offset += $size;
+    field
+  }};
+}
+
+macro_rules! take_uint {
+  ($buf:expr, $offset:expr, $size:expr, $name:expr) => {{
+    let start = $offset;
+    let value = c_uint_ascii($buf, start, $size).map_err(|e| match e {
+      NitfError::NotEnoughData { .. } => NitfError::NotEnoughData { field: $name, offset: start },
+      other => other,
+    })?;
+    $offset += $size;
+    value
+  }};
+}
+
+/// Parses the fields of an image subheader needed to decode its pixels.
+/// `buf` must start at the first byte of the subheader (`IM`).
+pub fn image_subheader(buf: &[u8]) -> Result<ImageSubheader, NitfError> {
+  let mut offset = 0usize;
+
+  take!(buf, offset, IM_SIZE, "IM");
+  take!(buf, offset, IID1_SIZE, "IID1");
+  take!(buf, offset, IDATIM_SIZE, "IDATIM");
+  take!(buf, offset, TGTID_SIZE, "TGTID");
+  take!(buf, offset, IID2_SIZE, "IID2");
+  take!(buf, offset, ISCLAS_SIZE, "ISCLAS");
+  take!(buf, offset, ISCLSY_SIZE, "ISCLSY");
+  take!(buf, offset, ISCODE_SIZE, "ISCODE");
+  take!(buf, offset, ISCTLH_SIZE, "ISCTLH");
+  take!(buf, offset, ISREL_SIZE, "ISREL");
+  take!(buf, offset, ISDCTP_SIZE, "ISDCTP");
+  take!(buf, offset, ISDCDT_SIZE, "ISDCDT");
+  take!(buf, offset, ISDCXM_SIZE, "ISDCXM");
+  take!(buf, offset, ISDG_SIZE, "ISDG");
+  take!(buf, offset, ISDGDT_SIZE, "ISDGDT");
+  take!(buf, offset, ISCLTX_SIZE, "ISCLTX");
+  take!(buf, offset, ISCATP_SIZE, "ISCATP");
+  take!(buf, offset, ISCAUT_SIZE, "ISCAUT");
+  take!(buf, offset, ISCRSN_SIZE, "ISCRSN");
+  take!(buf, offset, ISSRDT_SIZE, "ISSRDT");
+  take!(buf, offset, ISCTLN_SIZE, "ISCTLN");
+  take!(buf, offset, ENCRYP_SIZE, "ENCRYP");
+  take!(buf, offset, ISORCE_SIZE, "ISORCE");
+
+  let nrows = take_uint!(buf, offset, NROWS_SIZE, "NROWS") as u32;
+  let ncols = take_uint!(buf, offset, NCOLS_SIZE, "NCOLS") as u32;
+
+  take!(buf, offset, PVTYPE_SIZE, "PVTYPE");
+  take!(buf, offset, IREP_SIZE, "IREP");
+  take!(buf, offset, ICAT_SIZE, "ICAT");
+  take!(buf, offset, ABPP_SIZE, "ABPP");
+  take!(buf, offset, PJUST_SIZE, "PJUST");
+  let icords = take!(buf, offset, ICORDS_SIZE, "ICORDS")[0];
+
+  if icords != b' ' {
+    take!(buf, offset, IGEOLO_SIZE, "IGEOLO");
+  }
+
+  let nicom = take_uint!(buf, offset, NICOM_SIZE, "NICOM");
+  for _ in 0..nicom {
+    take!(buf, offset, ICOM_SIZE, "ICOM");
+  }
+
+  let ic_field = take!(buf, offset, IC_SIZE, "IC");
+  let ic = [ic_field[0], ic_field[1]];
+
+  if ic != *b"NC" && ic != *b"NM" {
+    take!(buf, offset, COMRAT_SIZE, "COMRAT");
+  }
+
+  let mut nbands = take_uint!(buf, offset, NBANDS_SIZE, "NBANDS") as u32;
+  if nbands == 0 {
+    nbands = take_uint!(buf, offset, XBANDS_SIZE, "XBANDS") as u32;
+  }
+
+  for _ in 0..nbands {
+    take!(buf, offset, IREPBAND_SIZE, "IREPBAND");
+    take!(buf, offset, ISUBCAT_SIZE, "ISUBCAT");
+    take!(buf, offset, IFC_SIZE, "IFC");
+    take!(buf, offset, IMFLT_SIZE, "IMFLT");
+    let nluts = take_uint!(buf, offset, NLUTS_SIZE, "NLUTS");
+    if nluts > 0 {
+      let nelut = take_uint!(buf, offset, NELUT_SIZE, "NELUT");
+      let lutd_size = (nluts * nelut) as usize;
+      take!(buf, offset, lutd_size, "LUTD");
+    }
+  }
+
+  take!(buf, offset, ISYNC_SIZE, "ISYNC");
+  let imode = take!(buf, offset, IMODE_SIZE, "IMODE")[0];
+  let nbpr = take_uint!(buf, offset, NBPR_SIZE, "NBPR") as u32;
+  let nbpc = take_uint!(buf, offset, NBPC_SIZE, "NBPC") as u32;
+  let nppbh = take_uint!(buf, offset, NPPBH_SIZE, "NPPBH") as u32;
+  let nppbv = take_uint!(buf, offset, NPPBV_SIZE, "NPPBV") as u32;
+  let nbpp = c_uint_ascii(buf, offset, NBPP_SIZE).map_err(|e| match e {
+    NitfError::NotEnoughData { .. } => NitfError::NotEnoughData { field: "NBPP", offset: offset },
+    other => other,
+  })? as u32;
+
+  Ok(ImageSubheader {
+    nrows: nrows,
+    ncols: ncols,
+    nbands: nbands,
+    nbpp: nbpp,
+    nbpr: nbpr,
+    nbpc: nbpc,
+    nppbh: nppbh,
+    nppbv: nppbv,
+    imode: imode,
+    ic: ic,
+  })
+}
+
+/// Re-orders a block's raw bytes (one contiguous chunk covering all bands)
+/// into pixel-interleaved order, honoring `hdr.imode`. Does not handle `S`
+/// (band sequential), since that interleaving spans the whole data field
+/// rather than a single block.
+fn deinterleave(hdr: &ImageSubheader, raw: &[u8]) -> Result<Vec<u8>, NitfError> {
+  let (block_w, block_h) = hdr.block_dims();
+  let nbands = hdr.nbands as usize;
+  let pixels_per_block = block_w * block_h;
+
+  let mut out = vec![0u8; pixels_per_block * nbands];
+
+  match hdr.imode {
+    b'P' => out.copy_from_slice(raw),
+    b'B' => {
+      for band in 0..nbands {
+        let plane = &raw[band * pixels_per_block..(band + 1) * pixels_per_block];
+        for i in 0..pixels_per_block {
+          out[i * nbands + band] = plane[i];
+        }
+      }
+    }
+    b'R' => {
+      for row in 0..block_h {
+        let row_start = row * block_w * nbands;
+        for band in 0..nbands {
+          let band_row = &raw[row_start + band * block_w..row_start + (band + 1) * block_w];
+          for col in 0..block_w {
+            out[(row * block_w + col) * nbands + band] = band_row[col];
+          }
+        }
+      }
+    }
+    _ => return Err(NitfError::Unsupported),
+  }
+
+  Ok(out)
+}
+
+/// Gathers block `block_index`'s bytes out of an uncompressed `data` field
+/// and de-interleaves them into pixel-interleaved order.
+fn read_block(hdr: &ImageSubheader, data: &[u8], block_index: usize) -> Result<Vec<u8>, NitfError> {
+  let (block_w, block_h) = hdr.block_dims();
+  let nbands = hdr.nbands as usize;
+  let nbpr = hdr.nbpr as usize;
+  let nbpc = hdr.nbpc as usize;
+  let pixels_per_block = block_w * block_h;
+  let block_size = pixels_per_block * nbands;
+
+  if hdr.imode == b'S' {
+    let mut out = vec![0u8; block_size];
+    let plane_size = nbpr * nbpc * pixels_per_block;
+
+    for band in 0..nbands {
+      let start = band * plane_size + block_index * pixels_per_block;
+      let plane = data.get(start..start + pixels_per_block).ok_or(NitfError::Unsupported)?;
+      for i in 0..pixels_per_block {
+        out[i * nbands + band] = plane[i];
+      }
+    }
+
+    return Ok(out);
+  }
+
+  let start = block_index * block_size;
+  let raw = data.get(start..start + block_size).ok_or(NitfError::Unsupported)?;
+  deinterleave(hdr, raw)
+}
+
+/// Stitches per-block pixel-interleaved byte buffers, as produced by
+/// `get_block`, back into a single `NCOLS`x`NROWS` raster.
+fn stitch_blocks<F>(hdr: &ImageSubheader, mut get_block: F) -> Result<Image, NitfError>
+where F: FnMut(usize) -> Result<Vec<u8>, NitfError> {
+  let (block_w, block_h) = hdr.block_dims();
+  let nbands = hdr.nbands as usize;
+  let nbpr = hdr.nbpr as usize;
+  let nbpc = hdr.nbpc as usize;
+  let w = hdr.ncols as usize;
+  let h = hdr.nrows as usize;
+
+  let mut raster = vec![0u8; w * h * nbands];
+
+  for block_row in 0..nbpc {
+    for block_col in 0..nbpr {
+      let block_index = block_row * nbpr + block_col;
+      let block = get_block(block_index)?;
+
+      for local_row in 0..block_h {
+        let global_row = block_row * block_h + local_row;
+        if global_row >= h {
+          continue;
+        }
+
+        for local_col in 0..block_w {
+          let global_col = block_col * block_w + local_col;
+          if global_col >= w {
+            continue;
+          }
+
+          let block_base = (local_row * block_w + local_col) * nbands;
+          let raster_base = (global_row * w + global_col) * nbands;
+          raster[raster_base..raster_base + nbands]
+            .copy_from_slice(&block[block_base..block_base + nbands]);
+        }
+      }
+    }
+  }
+
+  Ok(Image {
+    w: w,
+    h: h,
+    pixels: raster.chunks(nbands).map(|c| Pixel { bands: c.to_vec() }).collect(),
+  })
+}
+
+/// Decodes an uncompressed (`IC == "NC"`) image segment's data field.
+fn decode_uncompressed(hdr: &ImageSubheader, data: &[u8]) -> Result<Image, NitfError> {
+  if hdr.nbpp != 8 {
+    return Err(NitfError::Unsupported);
+  }
+
+  stitch_blocks(hdr, |block_index| read_block(hdr, data, block_index))
+}
+
+/// PackBits-style run-length decoding, as used for masked NITF blocks.
+/// Reads control bytes from `input` until exactly `expected_len` output
+/// bytes have been produced: `0..=127` copies the next `n + 1` bytes
+/// literally, `129..=255` repeats the following byte `257 - n` times, and
+/// `128` is a no-op.
+fn decode_packbits(input: &[u8], expected_len: usize) -> Result<Vec<u8>, NitfError> {
+  let mut out = Vec::with_capacity(expected_len);
+  let mut i = 0;
+
+  while out.len() < expected_len {
+    let n = *input.get(i).ok_or(NitfError::Unsupported)?;
+    i += 1;
+
+    if n <= 127 {
+      let count = n as usize + 1;
+      let literal = input.get(i..i + count).ok_or(NitfError::Unsupported)?;
+      out.extend_from_slice(literal);
+      i += count;
+    } else if n == 128 {
+      // No-op.
+    } else {
+      let count = 257 - n as usize;
+      let byte = *input.get(i).ok_or(NitfError::Unsupported)?;
+      i += 1;
+      for _ in 0..count {
+        out.push(byte);
+      }
+    }
+  }
+
+  if out.len() != expected_len {
+    return Err(NitfError::Unsupported);
+  }
+
+  Ok(out)
+}
+
+const MASK_ABSENT: u32 = 0xFFFF_FFFF;
+
+fn be_u16(buf: &[u8], offset: usize) -> Result<u16, NitfError> {
+  let b = c_field(buf, offset, 2)?;
+  Ok(((b[0] as u16) << 8) | b[1] as u16)
+}
+
+fn be_u32(buf: &[u8], offset: usize) -> Result<u32, NitfError> {
+  let b = c_field(buf, offset, 4)?;
+  Ok(((b[0] as u32) << 24) | ((b[1] as u32) << 16) | ((b[2] as u32) << 8) | b[3] as u32)
+}
+
+/// The image-data mask subheader that precedes the block data for `IC`
+/// codes starting with `M`: per-block offsets into the data field (with
+/// `MASK_ABSENT` marking blocks that were dropped entirely) and the pad
+/// pixel value absent blocks should be filled with.
+struct MaskTable<'a> {
+  imdatoff: u32,
+  block_offsets: Vec<u32>,
+  pad_value: &'a [u8],
+}
+
+fn parse_mask_table<'a>(data: &'a [u8], hdr: &ImageSubheader) -> Result<MaskTable<'a>, NitfError> {
+  let mut offset = 0usize;
+
+  let imdatoff = be_u32(data, offset)?;
+  offset += 4;
+  let bmrlnth = be_u16(data, offset)?;
+  offset += 2;
+  let tmrlnth = be_u16(data, offset)?;
+  offset += 2;
+  let tpxcdlnth = be_u16(data, offset)?;
+  offset += 2;
+
+  let pad_bytes = (tpxcdlnth as usize).div_ceil(8);
+  let pad_value = c_field(data, offset, pad_bytes)?;
+  offset += pad_bytes;
+
+  let blocks_per_plane = hdr.nbpr as usize * hdr.nbpc as usize;
+  let num_block_entries = if hdr.imode == b'S' {
+    blocks_per_plane * hdr.nbands as usize
+  } else {
+    blocks_per_plane
+  };
+
+  let mut block_offsets = Vec::with_capacity(num_block_entries);
+  if bmrlnth > 0 {
+    for _ in 0..num_block_entries {
+      block_offsets.push(be_u32(data, offset)?);
+      offset += 4;
+    }
+  }
+
+  if tmrlnth > 0 {
+    c_field(data, offset, num_block_entries * 4)?;
+  }
+
+  Ok(MaskTable { imdatoff: imdatoff, block_offsets: block_offsets, pad_value: pad_value })
+}
+
+fn pad_block(pixels_per_block: usize, pad_pixel: &[u8]) -> Vec<u8> {
+  let mut out = Vec::with_capacity(pixels_per_block * pad_pixel.len());
+  for _ in 0..pixels_per_block {
+    out.extend_from_slice(pad_pixel);
+  }
+  out
+}
+
+/// Decodes a masked, PackBits-compressed image segment (`IC` starting with
+/// `M`): parses the image-data mask subheader, fills masked-out blocks with
+/// the pad pixel value, and PackBits-decodes the rest before stitching.
+fn decode_masked(hdr: &ImageSubheader, data: &[u8]) -> Result<Image, NitfError> {
+  if hdr.nbpp != 8 {
+    return Err(NitfError::Unsupported);
+  }
+  if hdr.imode == b'S' {
+    return Err(NitfError::Unsupported);
+  }
+
+  let (block_w, block_h) = hdr.block_dims();
+  let pixels_per_block = block_w * block_h;
+  let block_size = pixels_per_block * hdr.nbands as usize;
+
+  let mask = parse_mask_table(data, hdr)?;
+  let image_data = data.get(mask.imdatoff as usize..).ok_or(NitfError::Unsupported)?;
+
+  if mask.pad_value.len() < hdr.nbands as usize {
+    return Err(NitfError::Unsupported);
+  }
+  let pad_pixel = pad_block(pixels_per_block, &mask.pad_value[..hdr.nbands as usize]);
+
+  stitch_blocks(hdr, |block_index| {
+    let absent = mask.block_offsets.get(block_index) == Some(&MASK_ABSENT);
+    if absent {
+      return Ok(pad_pixel.clone());
+    }
+
+    let start = match mask.block_offsets.get(block_index) {
+      Some(&offset) => offset as usize,
+      None => block_index * block_size,
+    };
+
+    let compressed = image_data.get(start..).ok_or(NitfError::Unsupported)?;
+    let raw = decode_packbits(compressed, block_size)?;
+    deinterleave(hdr, &raw)
+  })
+}
+
+/// Decodes an image segment's data field into a pixel raster, stitching
+/// `NPPBH`x`NPPBV` blocks back into a single `NCOLS`x`NROWS` image and
+/// honoring `IMODE` band interleaving. Handles uncompressed (`IC == "NC"`)
+/// and masked/PackBits-compressed (`IC` starting with `M`) segments;
+/// everything else is `NitfError::Unsupported`.
+pub fn decode_image(hdr: &ImageSubheader, data: &[u8]) -> Result<Image, NitfError> {
+  if hdr.ic == *b"NC" {
+    decode_uncompressed(hdr, data)
+  } else if hdr.ic[0] == b'M' {
+    decode_masked(hdr, data)
+  } else {
+    Err(NitfError::Unsupported)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn subheader(w: u32, h: u32, nbands: u32, imode: u8) -> ImageSubheader {
+    ImageSubheader {
+      nrows: h,
+      ncols: w,
+      nbands: nbands,
+      nbpp: 8,
+      nbpr: 1,
+      nbpc: 1,
+      nppbh: w,
+      nppbv: h,
+      imode: imode,
+      ic: *b"NC",
+    }
+  }
+
+  #[test]
+  fn test_decode_pixel_interleaved() {
+    let hdr = subheader(2, 1, 2, b'P');
+    let data = vec![1, 2, 3, 4];
+
+    let image = decode_image(&hdr, &data).unwrap();
+
+    assert_eq!(2, image.w);
+    assert_eq!(1, image.h);
+    assert_eq!(vec![1, 2], image.pixels[0].bands);
+    assert_eq!(vec![3, 4], image.pixels[1].bands);
+  }
+
+  #[test]
+  fn test_decode_band_interleaved_by_block() {
+    let hdr = subheader(2, 1, 2, b'B');
+    // band0 plane: [1, 3], band1 plane: [2, 4]
+    let data = vec![1, 3, 2, 4];
+
+    let image = decode_image(&hdr, &data).unwrap();
+
+    assert_eq!(vec![1, 2], image.pixels[0].bands);
+    assert_eq!(vec![3, 4], image.pixels[1].bands);
+  }
+
+  #[test]
+  fn test_decode_treats_zero_block_dims_as_full_image() {
+    let mut hdr = subheader(2, 1, 2, b'P');
+    hdr.nppbh = 0;
+    hdr.nppbv = 0;
+    let data = vec![1, 2, 3, 4];
+
+    let image = decode_image(&hdr, &data).unwrap();
+
+    assert_eq!(2, image.w);
+    assert_eq!(1, image.h);
+    assert_eq!(vec![1, 2], image.pixels[0].bands);
+    assert_eq!(vec![3, 4], image.pixels[1].bands);
+  }
+
+  #[test]
+  fn test_decode_rejects_unsupported_compression() {
+    let mut hdr = subheader(1, 1, 1, b'P');
+    hdr.ic = *b"C1";
+
+    let err = decode_image(&hdr, &[0]).unwrap_err();
+
+    assert_eq!(NitfError::Unsupported, err);
+  }
+
+  #[test]
+  fn test_decode_packbits_literal_repeat_and_noop() {
+    // literal run of 2, repeat-run of 2, a no-op, then a literal run of 1.
+    let input = [1, 10, 20, 255, 5, 128, 0, 7];
+
+    let out = decode_packbits(&input, 5).unwrap();
+
+    assert_eq!(vec![10, 20, 5, 5, 7], out);
+  }
+
+  fn masked_mask_header(imdatoff: u32, bmrlnth: u16, pad: u8, block_offset: u32) -> Vec<u8> {
+    let mut header = Vec::new();
+    header.extend_from_slice(&[
+      (imdatoff >> 24) as u8, (imdatoff >> 16) as u8, (imdatoff >> 8) as u8, imdatoff as u8,
+    ]);
+    header.extend_from_slice(&[(bmrlnth >> 8) as u8, bmrlnth as u8]);
+    header.extend_from_slice(&[0, 0]); // TMRLNTH
+    header.extend_from_slice(&[0, 8]); // TPXCDLNTH (1 byte of pad value)
+    header.push(pad);
+    header.extend_from_slice(&[
+      (block_offset >> 24) as u8, (block_offset >> 16) as u8,
+      (block_offset >> 8) as u8, block_offset as u8,
+    ]);
+    header
+  }
+
+  #[test]
+  fn test_decode_masked_packbits_block() {
+    let mut hdr = subheader(1, 1, 1, b'P');
+    hdr.ic = *b"M8";
+
+    let mut data = masked_mask_header(15, 4, 99, 0);
+    data.extend_from_slice(&[0, 42]); // literal run of 1: value 42
+
+    let image = decode_image(&hdr, &data).unwrap();
+
+    assert_eq!(vec![42], image.pixels[0].bands);
+  }
+
+  #[test]
+  fn test_decode_masked_absent_block_uses_pad_value() {
+    let mut hdr = subheader(1, 1, 1, b'P');
+    hdr.ic = *b"M8";
+
+    let data = masked_mask_header(15, 4, 99, MASK_ABSENT);
+
+    let image = decode_image(&hdr, &data).unwrap();
+
+    assert_eq!(vec![99], image.pixels[0].bands);
+  }
+}
@@ -27,17 +27,24 @@
 //! References:
 //! # [MIL-STD-2500C](http://www.gwg.nga.mil/ntb/baseline/docs/2500c/2500C.pdf)
 
-#[macro_use]
-extern crate nom;
 extern crate memmap;
 
-use std::borrow::{Borrow, Cow};
+mod bin_util;
+mod error;
+mod image;
+mod repr;
+mod segment;
+
 use std::str;
-use std::str::FromStr;
 use std::fs::File;
-use std::num::ParseIntError;
-use nom::{IResult, be_u8, be_u32, digit};
-use memmap::{Mmap, MmapOptions};
+
+use memmap::MmapOptions;
+
+pub use error::NitfError;
+pub use image::{Image, ImageSubheader, Pixel, decode_image, image_subheader};
+pub use repr::{Classification, ClassificationAuthorityType, ClassificationReason, Encryption};
+pub use segment::{Segment, SegmentKind, segments};
+use bin_util::{c_field, optional_trimmed_str, parse_ascii_uint, trimmed_str};
 
 const FHDR_SIZE: usize = 4;
 const FVER_SIZE: usize = 5;
@@ -73,6 +80,19 @@ const HL_SIZE: usize = 6;
 const NUMI_SIZE: usize = 3;
 const LISH_SIZE: usize = 6;
 const LI_SIZE: usize = 10;
+const NUMS_SIZE: usize = 3;
+const LSSH_SIZE: usize = 4;
+const LS_SIZE: usize = 6;
+const NUMX_SIZE: usize = 3;
+const NUMT_SIZE: usize = 3;
+const LTSH_SIZE: usize = 4;
+const LT_SIZE: usize = 5;
+const NUMDES_SIZE: usize = 3;
+const LDSH_SIZE: usize = 4;
+const LD_SIZE: usize = 9;
+const NUMRES_SIZE: usize = 3;
+const LRSH_SIZE: usize = 4;
+const LR_SIZE: usize = 7;
 
 
 #[derive(Debug)]
@@ -80,158 +100,306 @@ pub struct RGB(u8, u8, u8);
 
 #[derive(Debug)]
 pub struct NitfHeader<'a> {
-  fhdr: &'a [u8],
-  fver: &'a [u8],
-  clevel: &'a [u8],
-  stype: &'a [u8],
-  ostaid: &'a [u8],
-  fdt: &'a [u8],
-  ftitle: &'a [u8],
-  fsclass: &'a [u8],
-  fsclsy: &'a [u8],
-  fscode: &'a [u8],
-  fsctlh: &'a [u8],
-  fsrel: &'a [u8],
-  fsdctp: &'a [u8],
-  fsdcdt: &'a [u8],
-  fsdcxm: &'a [u8],
-  fsdg: &'a [u8],
-  fsdgdt: &'a [u8],
-  fscltx: &'a [u8],
-  fscatp: &'a [u8],
-  fscaut: &'a [u8],
-  fscrsn: &'a [u8],
-  fssrdt: &'a [u8],
-  fsctln: &'a [u8],
-  fscop: &'a [u8],
-  fscpys: &'a [u8],
-  encryp: &'a [u8],
-  fbkgc: RGB,
-  oname: &'a [u8],
-  ophone: &'a [u8],
-  fl: &'a [u8],
-  hl: &'a [u8],
-  numi: &'a [u8],
-  lish: Vec<&'a[u8]>,
-  li: Vec<&'a[u8]>,
+  pub(crate) fhdr: &'a [u8],
+  pub(crate) fver: &'a [u8],
+  pub(crate) clevel: &'a [u8],
+  pub(crate) stype: &'a [u8],
+  pub(crate) ostaid: &'a [u8],
+  pub(crate) fdt: &'a [u8],
+  pub(crate) ftitle: &'a [u8],
+  pub(crate) fsclass: &'a [u8],
+  pub(crate) fsclsy: &'a [u8],
+  pub(crate) fscode: &'a [u8],
+  pub(crate) fsctlh: &'a [u8],
+  pub(crate) fsrel: &'a [u8],
+  pub(crate) fsdctp: &'a [u8],
+  pub(crate) fsdcdt: &'a [u8],
+  pub(crate) fsdcxm: &'a [u8],
+  pub(crate) fsdg: &'a [u8],
+  pub(crate) fsdgdt: &'a [u8],
+  pub(crate) fscltx: &'a [u8],
+  pub(crate) fscatp: &'a [u8],
+  pub(crate) fscaut: &'a [u8],
+  pub(crate) fscrsn: &'a [u8],
+  pub(crate) fssrdt: &'a [u8],
+  pub(crate) fsctln: &'a [u8],
+  pub(crate) fscop: &'a [u8],
+  pub(crate) fscpys: &'a [u8],
+  pub(crate) encryp: &'a [u8],
+  pub(crate) fbkgc: RGB,
+  pub(crate) oname: &'a [u8],
+  pub(crate) ophone: &'a [u8],
+  pub(crate) fl: &'a [u8],
+  pub(crate) hl: &'a [u8],
+  pub(crate) numi: &'a [u8],
+  pub(crate) lish: Vec<&'a[u8]>,
+  pub(crate) li: Vec<&'a[u8]>,
+  pub(crate) nums: &'a [u8],
+  pub(crate) lssh: Vec<&'a[u8]>,
+  pub(crate) ls: Vec<&'a[u8]>,
+  pub(crate) numx: &'a [u8],
+  pub(crate) numt: &'a [u8],
+  pub(crate) ltsh: Vec<&'a[u8]>,
+  pub(crate) lt: Vec<&'a[u8]>,
+  pub(crate) numdes: &'a [u8],
+  pub(crate) ldsh: Vec<&'a[u8]>,
+  pub(crate) ld: Vec<&'a[u8]>,
+  pub(crate) numres: &'a [u8],
+  pub(crate) lrsh: Vec<&'a[u8]>,
+  pub(crate) lr: Vec<&'a[u8]>,
 }
 
 
-named!(
-  parse_fbkgc <&[u8], RGB>,
-  map!(
-    take!(FBKGC_SIZE),
-    |rgb: &[u8]| RGB(rgb[0], rgb[1], rgb[2])
-  )
-);
-
-named!(num_from_str <&str, Result<i8,ParseIntError>>,
-    map!(digit, FromStr::from_str)
-);
+/// Pulls `$size` bytes out of `$buf` at the running `$offset`, naming the
+/// field in the error if there isn't enough data, and advances `$offset`
+/// past it.
+macro_rules! take_field {
+  ($buf:expr, $offset:expr, $size:expr, $name:expr) => {{
+    let start = $offset;
+    let field = c_field($buf, start, $size)
+      .map_err(|_| NitfError::NotEnoughData { field: $name, offset: start })?;
+    $offset += $size;
+    field
+  }};
+}
 
-fn parse_lish_and_li<'a>(input: &[u8], numi: &[u8]) -> IResult<&'a[u8], &'a[u8]> {
-  let numi_str = String::from_utf8_lossy(numi).borrow();
-  let mut num_lish: usize = match num_from_str(numi_str) {
-    IResult::Done(_, Ok(num)) => num as usize,
-    _ => panic!("unable to parse numi")
-  };
+/// The per-segment subheader-length fields, data-length fields, and the
+/// offset just past the last pair, as returned by `parse_segment_lengths`.
+type SegmentLengths<'a> = (Vec<&'a [u8]>, Vec<&'a [u8]>, usize);
 
-  let mut lish_vec: Vec<&[u8]> = Vec::new();
-  let mut li_vec: Vec<&[u8]> = Vec::new();
+/// Parses the repeated `(subheader-length, data-length)` pairs that follow a
+/// `NUMx` count field (e.g. `NUMI`+`LISHn`/`LIn`, `NUMS`+`LSSHn`/`LSn`, ...),
+/// starting at `offset`. Returns the collected pairs along with the offset
+/// just past them.
+fn parse_segment_lengths<'a>(
+  buf: &'a [u8],
+  mut offset: usize,
+  count: &[u8],
+  sh_size: usize,
+  sh_name: &'static str,
+  len_size: usize,
+  len_name: &'static str,
+) -> Result<SegmentLengths<'a>, NitfError> {
+  let num = parse_ascii_uint(count)?;
 
-  for x in 1..num_lish {
+  let mut sh_vec = Vec::with_capacity(num as usize);
+  let mut len_vec = Vec::with_capacity(num as usize);
 
-    // Need to work on this part below, right now won't compile!
-    map!(
-    take!(LISH_SIZE),
-    |bytes| lish_vec.push(bytes)
-    );
-    map!(
-      take!(LI_SIZE),
-      |bytes| li_vec.push(bytes)
-    );
+  for _ in 0..num {
+    let sh = take_field!(buf, offset, sh_size, sh_name);
+    let len = take_field!(buf, offset, len_size, len_name);
+    sh_vec.push(sh);
+    len_vec.push(len);
   }
 
-  (lish_vec, li_vec)
+  Ok((sh_vec, len_vec, offset))
 }
 
+pub fn header(buf: &[u8]) -> Result<NitfHeader, NitfError> {
+  let mut offset = 0usize;
+
+  let fhdr = take_field!(buf, offset, FHDR_SIZE, "FHDR");
+  let fver = take_field!(buf, offset, FVER_SIZE, "FVER");
+  let clevel = take_field!(buf, offset, CLEVEL_SIZE, "CLEVEL");
+  let stype = take_field!(buf, offset, STYPE_SIZE, "STYPE");
+  let ostaid = take_field!(buf, offset, OSTAID_SIZE, "OSTAID");
+  let fdt = take_field!(buf, offset, FDT_SIZE, "FDT");
+  let ftitle = take_field!(buf, offset, FTITLE_SIZE, "FTITLE");
+  let fsclass = take_field!(buf, offset, FSCLASS_SIZE, "FSCLAS");
+  let fsclsy = take_field!(buf, offset, FSCLSY_SIZE, "FSCLSY");
+  let fscode = take_field!(buf, offset, FSCODE_SIZE, "FSCODE");
+  let fsctlh = take_field!(buf, offset, FSCTLH_SIZE, "FSCTLH");
+  let fsrel = take_field!(buf, offset, FSREL_SIZE, "FSREL");
+  let fsdctp = take_field!(buf, offset, FSDCTP_SIZE, "FSDCTP");
+  let fsdcdt = take_field!(buf, offset, FSDCDT_SIZE, "FSDCDT");
+  let fsdcxm = take_field!(buf, offset, FSDCXM_SIZE, "FSDCXM");
+  let fsdg = take_field!(buf, offset, FSDG_SIZE, "FSDG");
+  let fsdgdt = take_field!(buf, offset, FSDGDT_SIZE, "FSDGDT");
+  let fscltx = take_field!(buf, offset, FSCLTX_SIZE, "FSCLTX");
+  let fscatp = take_field!(buf, offset, FSCATP_SIZE, "FSCATP");
+  let fscaut = take_field!(buf, offset, FSCAUT_SIZE, "FSCAUT");
+  let fscrsn = take_field!(buf, offset, FSCRSN_SIZE, "FSCRSN");
+  let fssrdt = take_field!(buf, offset, FSSRDT_SIZE, "FSSRDT");
+  let fsctln = take_field!(buf, offset, FSCTLN_SIZE, "FSCTLN");
+  let fscop = take_field!(buf, offset, FSCOP_SIZE, "FSCOP");
+  let fscpys = take_field!(buf, offset, FSCPYS_SIZE, "FSCPYS");
+  let encryp = take_field!(buf, offset, ENCRYP_SIZE, "ENCRYP");
+  let fbkgc_bytes = take_field!(buf, offset, FBKGC_SIZE, "FBKGC");
+  let fbkgc = RGB(fbkgc_bytes[0], fbkgc_bytes[1], fbkgc_bytes[2]);
+  let oname = take_field!(buf, offset, ONAME_SIZE, "ONAME");
+  let ophone = take_field!(buf, offset, OPHONE_SIZE, "OPHONE");
+  let fl = take_field!(buf, offset, FL_SIZE, "FL");
+  let hl = take_field!(buf, offset, HL_SIZE, "HL");
+  let numi = take_field!(buf, offset, NUMI_SIZE, "NUMI");
+  let (lish, li, next) = parse_segment_lengths(
+    buf, offset, numi, LISH_SIZE, "LISHn", LI_SIZE, "LIn")?;
+  offset = next;
+
+  let nums = take_field!(buf, offset, NUMS_SIZE, "NUMS");
+  let (lssh, ls, next) = parse_segment_lengths(
+    buf, offset, nums, LSSH_SIZE, "LSSHn", LS_SIZE, "LSn")?;
+  offset = next;
+
+  // NUMX is reserved for future use and is always "000", but its bytes
+  // still have to be consumed to keep the rest of the header aligned.
+  let numx = take_field!(buf, offset, NUMX_SIZE, "NUMX");
+
+  let numt = take_field!(buf, offset, NUMT_SIZE, "NUMT");
+  let (ltsh, lt, next) = parse_segment_lengths(
+    buf, offset, numt, LTSH_SIZE, "LTSHn", LT_SIZE, "LTn")?;
+  offset = next;
 
-pub fn header(input: &[u8]) -> IResult<&[u8], NitfHeader> {
-  do_parse!(input,
-  fhdr: take!(FHDR_SIZE) >>
-  fver: take!(FVER_SIZE) >>
-  clevel: take!(CLEVEL_SIZE) >>
-  stype: take!(STYPE_SIZE) >>
-  ostaid: take!(OSTAID_SIZE) >>
-  fdt: take!(FDT_SIZE) >>
-  ftitle: take!(FTITLE_SIZE) >>
-  fsclass: take!(FSCLASS_SIZE) >>
-  fsclsy: take!(FSCLSY_SIZE) >>
-  fscode: take!(FSCODE_SIZE) >>
-  fsctlh: take!(FSCTLH_SIZE) >>
-  fsrel: take!(FSREL_SIZE) >>
-  fsdctp: take!(FSDCTP_SIZE) >>
-  fsdcdt: take!(FSDCDT_SIZE) >>
-  fsdcxm: take!(FSDCXM_SIZE) >>
-  fsdg: take!(FSDG_SIZE) >>
-  fsdgdt: take!(FSDGDT_SIZE) >>
-  fscltx: take!(FSCLTX_SIZE) >>
-  fscatp: take!(FSCATP_SIZE) >>
-  fscaut: take!(FSCAUT_SIZE) >>
-  fscrsn: take!(FSCRSN_SIZE) >>
-  fssrdt: take!(FSSRDT_SIZE) >>
-  fsctln: take!(FSCTLN_SIZE) >>
-  fscop: take!(FSCOP_SIZE) >>
-  fscpys: take!(FSCPYS_SIZE) >>
-  encryp: take!(ENCRYP_SIZE) >>
-  fbkgc: parse_fbkgc >>
-  oname: take!(ONAME_SIZE) >>
-  ophone: take!(OPHONE_SIZE) >>
-  fl: take!(FL_SIZE) >>
-  hl: take!(HL_SIZE) >>
-  numi: take!(NUMI_SIZE) >>
-  (
-    NitfHeader {
-      fhdr: fhdr,
-      fver: fver,
-      clevel: clevel,
-      stype: stype,
-      ostaid: ostaid,
-      fdt: fdt,
-      ftitle: ftitle,
-      fsclass: fsclass,
-      fsclsy: fsclsy,
-      fscode: fscode,
-      fsctlh: fsctlh,
-      fsrel: fsrel,
-      fsdctp: fsdctp,
-      fsdcdt: fsdcdt,
-      fsdcxm: fsdcxm,
-      fsdg: fsdg,
-      fsdgdt: fsdgdt,
-      fscltx: fscltx,
-      fscatp: fscatp,
-      fscaut: fscaut,
-      fscrsn: fscrsn,
-      fssrdt: fssrdt,
-      fsctln: fsctln,
-      fscop: fscop,
-      fscpys: fscpys,
-      encryp: encryp,
-      fbkgc: fbkgc,
-      oname: oname,
-      ophone: ophone,
-      fl: fl,
-      hl: hl,
-      numi: numi,
+  let numdes = take_field!(buf, offset, NUMDES_SIZE, "NUMDES");
+  let (ldsh, ld, next) = parse_segment_lengths(
+    buf, offset, numdes, LDSH_SIZE, "LDSHn", LD_SIZE, "LDn")?;
+  offset = next;
+
+  let numres = take_field!(buf, offset, NUMRES_SIZE, "NUMRES");
+  let (lrsh, lr, _next) = parse_segment_lengths(
+    buf, offset, numres, LRSH_SIZE, "LRSHn", LR_SIZE, "LRn")?;
+
+  Ok(NitfHeader {
+    fhdr: fhdr,
+    fver: fver,
+    clevel: clevel,
+    stype: stype,
+    ostaid: ostaid,
+    fdt: fdt,
+    ftitle: ftitle,
+    fsclass: fsclass,
+    fsclsy: fsclsy,
+    fscode: fscode,
+    fsctlh: fsctlh,
+    fsrel: fsrel,
+    fsdctp: fsdctp,
+    fsdcdt: fsdcdt,
+    fsdcxm: fsdcxm,
+    fsdg: fsdg,
+    fsdgdt: fsdgdt,
+    fscltx: fscltx,
+    fscatp: fscatp,
+    fscaut: fscaut,
+    fscrsn: fscrsn,
+    fssrdt: fssrdt,
+    fsctln: fsctln,
+    fscop: fscop,
+    fscpys: fscpys,
+    encryp: encryp,
+    fbkgc: fbkgc,
+    oname: oname,
+    ophone: ophone,
+    fl: fl,
+    hl: hl,
+    numi: numi,
+    lish: lish,
+    li: li,
+    nums: nums,
+    lssh: lssh,
+    ls: ls,
+    numx: numx,
+    numt: numt,
+    ltsh: ltsh,
+    lt: lt,
+    numdes: numdes,
+    ldsh: ldsh,
+    ld: ld,
+    numres: numres,
+    lrsh: lrsh,
+    lr: lr,
   })
-  )
+}
+
+impl<'a> NitfHeader<'a> {
+  /// `FL`: the total length of the file, in bytes.
+  pub fn file_length(&self) -> Result<u64, NitfError> {
+    parse_ascii_uint(self.fl)
+  }
+
+  /// `HL`: the length of this file header, in bytes.
+  pub fn header_length(&self) -> Result<u64, NitfError> {
+    parse_ascii_uint(self.hl)
+  }
+
+  /// `CLEVEL`: the complexity level required to exploit the file.
+  pub fn complexity_level(&self) -> Result<u8, NitfError> {
+    parse_ascii_uint(self.clevel).map(|v| v as u8)
+  }
+
+  /// `NUMI`: the number of image segments.
+  pub fn num_images(&self) -> Result<u64, NitfError> {
+    parse_ascii_uint(self.numi)
+  }
+
+  /// `FDT`: the file's date and time of origination.
+  pub fn file_datetime(&self) -> Result<&str, NitfError> {
+    trimmed_str(self.fdt)
+  }
+
+  /// `FTITLE`: the file title.
+  pub fn file_title(&self) -> Result<&str, NitfError> {
+    trimmed_str(self.ftitle)
+  }
+
+  /// `STYPE`: the standard type, a free-form 4-character field rather than
+  /// a coded value, so it isn't a `c_enum!` candidate like `FSCLASS`.
+  pub fn stype(&self) -> Result<&str, NitfError> {
+    trimmed_str(self.stype)
+  }
+
+  /// `ONAME`: the originator's name.
+  pub fn originator_name(&self) -> Result<&str, NitfError> {
+    trimmed_str(self.oname)
+  }
+
+  /// `OPHONE`: the originator's phone number.
+  pub fn originator_phone(&self) -> Result<&str, NitfError> {
+    trimmed_str(self.ophone)
+  }
+
+  /// `FSDCDT`: the declassification date, or `None` if the field is all
+  /// spaces (not applicable for this file's declassification type).
+  pub fn declassification_date(&self) -> Result<Option<&str>, NitfError> {
+    optional_trimmed_str(self.fsdcdt)
+  }
+
+  /// `FSDG`: the downgrade-to classification, or `None` if not applicable.
+  pub fn downgrade(&self) -> Result<Option<&str>, NitfError> {
+    optional_trimmed_str(self.fsdg)
+  }
+
+  /// `FSCTLN`: the security control number, or `None` if not applicable.
+  pub fn security_control_number(&self) -> Result<Option<&str>, NitfError> {
+    optional_trimmed_str(self.fsctln)
+  }
+
+  /// `FSCLASS`: the file's security classification.
+  pub fn fsclass(&self) -> Result<Classification, NitfError> {
+    Classification::from_repr(self.fsclass)
+  }
+
+  /// `ENCRYP`: whether the file is encrypted.
+  pub fn encryp(&self) -> Result<Encryption, NitfError> {
+    Encryption::from_repr(self.encryp)
+  }
+
+  /// `FSCATP`: the authority type behind the classification.
+  pub fn fscatp(&self) -> Result<ClassificationAuthorityType, NitfError> {
+    ClassificationAuthorityType::from_repr(self.fscatp)
+  }
+
+  /// `FSCRSN`: the reason the file was classified.
+  pub fn fscrsn(&self) -> Result<ClassificationReason, NitfError> {
+    ClassificationReason::from_repr(self.fscrsn)
+  }
+
+  // `STYPE` ("standard type") is a free-form 4-character field, not a
+  // single coded value, so it isn't a `c_enum!` candidate; see `stype()`.
 }
 
 #[test]
 fn test_numi_from_str() {
-  let numi = num_from_str("003".as_bytes());
-  println!("num: {:?}", numi);
+  let numi = bin_util::parse_ascii_uint("003".as_bytes());
+  assert_eq!(Ok(3), numi);
 }
 
 #[test]
@@ -239,7 +407,7 @@ fn test_version() {
   let input = File::open("test/resources/i_3001a.ntf").expect("File does not exist");
   let mmap = unsafe { MmapOptions::new().map(&input).unwrap() };
 
-  let (_, nitf_hdr) = header(&mmap).unwrap();
+  let nitf_hdr = header(&mmap).unwrap();
 
   assert_eq!("NITF", str::from_utf8(nitf_hdr.fhdr).unwrap());
   assert_eq!("02.10", str::from_utf8(nitf_hdr.fver).unwrap());
@@ -248,3 +416,145 @@ fn test_version() {
   assert_eq!("JITC Fort Huachuca, AZ  ", str::from_utf8(nitf_hdr.oname).unwrap());
   assert_eq!("001", str::from_utf8(nitf_hdr.numi).unwrap());
 }
+
+#[test]
+fn test_header_reports_truncated_input() {
+  let input = [0u8; FHDR_SIZE - 1];
+
+  let err = header(&input).unwrap_err();
+
+  assert_eq!(NitfError::NotEnoughData { field: "FHDR", offset: 0 }, err);
+}
+
+#[test]
+fn test_segments_offsets_for_single_image() {
+  fn field(buf: &mut Vec<u8>, width: usize, text: &str) {
+    let mut bytes = text.as_bytes().to_vec();
+    bytes.resize(width, b' ');
+    buf.extend_from_slice(&bytes);
+  }
+
+  let mut buf = Vec::new();
+  field(&mut buf, FHDR_SIZE, "NITF");
+  field(&mut buf, FVER_SIZE, "02.10");
+  field(&mut buf, CLEVEL_SIZE, "03");
+  field(&mut buf, STYPE_SIZE, "BF01");
+  field(&mut buf, OSTAID_SIZE, "");
+  field(&mut buf, FDT_SIZE, "");
+  field(&mut buf, FTITLE_SIZE, "");
+  field(&mut buf, FSCLASS_SIZE, "U");
+  field(&mut buf, FSCLSY_SIZE, "");
+  field(&mut buf, FSCODE_SIZE, "");
+  field(&mut buf, FSCTLH_SIZE, "");
+  field(&mut buf, FSREL_SIZE, "");
+  field(&mut buf, FSDCTP_SIZE, "");
+  field(&mut buf, FSDCDT_SIZE, "");
+  field(&mut buf, FSDCXM_SIZE, "");
+  field(&mut buf, FSDG_SIZE, "");
+  field(&mut buf, FSDGDT_SIZE, "");
+  field(&mut buf, FSCLTX_SIZE, "");
+  field(&mut buf, FSCATP_SIZE, "");
+  field(&mut buf, FSCAUT_SIZE, "");
+  field(&mut buf, FSCRSN_SIZE, "");
+  field(&mut buf, FSSRDT_SIZE, "");
+  field(&mut buf, FSCTLN_SIZE, "");
+  field(&mut buf, FSCOP_SIZE, "");
+  field(&mut buf, FSCPYS_SIZE, "");
+  field(&mut buf, ENCRYP_SIZE, "0");
+  buf.extend_from_slice(&[0, 0, 0]); // FBKGC
+  field(&mut buf, ONAME_SIZE, "");
+  field(&mut buf, OPHONE_SIZE, "");
+  field(&mut buf, FL_SIZE, "000000000500");
+  field(&mut buf, HL_SIZE, "000394");
+  field(&mut buf, NUMI_SIZE, "001");
+  field(&mut buf, LISH_SIZE, "000100");
+  field(&mut buf, LI_SIZE, "0000000200");
+  field(&mut buf, NUMS_SIZE, "000");
+  field(&mut buf, NUMX_SIZE, "000");
+  field(&mut buf, NUMT_SIZE, "000");
+  field(&mut buf, NUMDES_SIZE, "000");
+  field(&mut buf, NUMRES_SIZE, "000");
+
+  let hdr = header(&buf).unwrap();
+  let segs = segments(&hdr).unwrap();
+
+  assert_eq!(1, segs.len());
+  assert_eq!(SegmentKind::Image, segs[0].kind);
+  assert_eq!(394, segs[0].subheader_offset);
+  assert_eq!(100, segs[0].subheader_len);
+  assert_eq!(494, segs[0].data_offset);
+  assert_eq!(200, segs[0].data_len);
+}
+
+#[test]
+fn test_typed_field_accessors() {
+  fn field(buf: &mut Vec<u8>, width: usize, text: &str) {
+    let mut bytes = text.as_bytes().to_vec();
+    bytes.resize(width, b' ');
+    buf.extend_from_slice(&bytes);
+  }
+
+  let mut buf = Vec::new();
+  field(&mut buf, FHDR_SIZE, "NITF");
+  field(&mut buf, FVER_SIZE, "02.10");
+  field(&mut buf, CLEVEL_SIZE, "03");
+  field(&mut buf, STYPE_SIZE, "BF01");
+  field(&mut buf, OSTAID_SIZE, "");
+  field(&mut buf, FDT_SIZE, "20260101120000");
+  field(&mut buf, FTITLE_SIZE, "Sample Title");
+  field(&mut buf, FSCLASS_SIZE, "U");
+  field(&mut buf, FSCLSY_SIZE, "");
+  field(&mut buf, FSCODE_SIZE, "");
+  field(&mut buf, FSCTLH_SIZE, "");
+  field(&mut buf, FSREL_SIZE, "");
+  field(&mut buf, FSDCTP_SIZE, "");
+  field(&mut buf, FSDCDT_SIZE, ""); // all spaces -> None
+  field(&mut buf, FSDCXM_SIZE, "");
+  field(&mut buf, FSDG_SIZE, ""); // all spaces -> None
+  field(&mut buf, FSDGDT_SIZE, "");
+  field(&mut buf, FSCLTX_SIZE, "");
+  field(&mut buf, FSCATP_SIZE, "O");
+  field(&mut buf, FSCAUT_SIZE, "");
+  field(&mut buf, FSCRSN_SIZE, "A");
+  field(&mut buf, FSSRDT_SIZE, "");
+  field(&mut buf, FSCTLN_SIZE, "ABC123");
+  field(&mut buf, FSCOP_SIZE, "");
+  field(&mut buf, FSCPYS_SIZE, "");
+  field(&mut buf, ENCRYP_SIZE, "0");
+  buf.extend_from_slice(&[0, 0, 0]); // FBKGC
+  field(&mut buf, ONAME_SIZE, "JITC Fort Huachuca, AZ");
+  field(&mut buf, OPHONE_SIZE, "555-0100");
+  field(&mut buf, FL_SIZE, "000000000500");
+  field(&mut buf, HL_SIZE, "000378");
+  field(&mut buf, NUMI_SIZE, "000");
+  field(&mut buf, NUMS_SIZE, "000");
+  field(&mut buf, NUMX_SIZE, "000");
+  field(&mut buf, NUMT_SIZE, "000");
+  field(&mut buf, NUMDES_SIZE, "000");
+  field(&mut buf, NUMRES_SIZE, "000");
+
+  let hdr = header(&buf).unwrap();
+
+  assert_eq!(3, hdr.complexity_level().unwrap());
+  assert_eq!(500, hdr.file_length().unwrap());
+  assert_eq!(378, hdr.header_length().unwrap());
+  assert_eq!("20260101120000", hdr.file_datetime().unwrap());
+  assert_eq!("Sample Title", hdr.file_title().unwrap());
+  assert_eq!("JITC Fort Huachuca, AZ", hdr.originator_name().unwrap());
+  assert_eq!("555-0100", hdr.originator_phone().unwrap());
+  assert_eq!(None, hdr.declassification_date().unwrap());
+  assert_eq!(None, hdr.downgrade().unwrap());
+  assert_eq!(Some("ABC123"), hdr.security_control_number().unwrap());
+  assert_eq!(Classification::Unclassified, hdr.fsclass().unwrap());
+  assert_eq!(Encryption::NotEncrypted, hdr.encryp().unwrap());
+  assert_eq!(ClassificationAuthorityType::Original, hdr.fscatp().unwrap());
+  assert_eq!(ClassificationReason::DirectFromOtherSource, hdr.fscrsn().unwrap());
+  assert_eq!("BF01", hdr.stype().unwrap());
+}
+
+#[test]
+fn test_fsclass_rejects_unknown_code() {
+  let err = Classification::from_repr(b"X").unwrap_err();
+
+  assert_eq!(NitfError::InvalidCode { field: "FSCLASS" }, err);
+}
@@ -0,0 +1,58 @@
+//! Typed accessors for NITF's single-byte coded fields.
+//!
+//! Fields like `FSCLASS` and `ENCRYP` aren't free-form text: each is one byte
+//! drawn from a small, fixed set of codes with its own meaning. `c_enum!`
+//! turns a `code => meaning` table into an enum plus a `from_repr` that
+//! matches the raw field and errors on anything outside the table, so
+//! callers can `match` on the typed value instead of comparing raw bytes.
+
+use error::NitfError;
+
+macro_rules! c_enum {
+  ($name:ident, $field_name:expr, { $($variant:ident => $code:expr),+ $(,)* }) => {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum $name {
+      $($variant),+
+    }
+
+    impl $name {
+      /// Matches the raw field bytes against this enum's codes, erroring
+      /// with `NitfError::InvalidCode` if none match.
+      pub fn from_repr(field: &[u8]) -> Result<Self, NitfError> {
+        match field {
+          $($code => Ok($name::$variant),)+
+          _ => Err(NitfError::InvalidCode { field: $field_name }),
+        }
+      }
+    }
+  };
+}
+
+c_enum!(Classification, "FSCLASS", {
+  TopSecret => b"T",
+  Secret => b"S",
+  Confidential => b"C",
+  Restricted => b"R",
+  Unclassified => b"U",
+});
+
+c_enum!(Encryption, "ENCRYP", {
+  NotEncrypted => b"0",
+  Encrypted => b"1",
+});
+
+c_enum!(ClassificationAuthorityType, "FSCATP", {
+  Original => b"O",
+  Derivative => b"D",
+  Multiple => b"M",
+});
+
+c_enum!(ClassificationReason, "FSCRSN", {
+  DirectFromOtherSource => b"A",
+  TranslatedDocument => b"B",
+  ExtractedDocument => b"C",
+  DirectedBySource => b"D",
+  DirectFromMultipleSources => b"E",
+  RequestedByOriginator => b"F",
+  ExemptFromDeclassification => b"G",
+});
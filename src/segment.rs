@@ -0,0 +1,87 @@
+//! Segment offset table.
+//!
+//! A `NitfHeader` only records, per segment, the length of its subheader and
+//! the length of its data field (as ASCII text, straight off the wire). This
+//! module turns those lengths into absolute file offsets by running the
+//! cumulative sum described in MIL-STD-2500C, starting right after the file
+//! header (`HL`).
+
+use bin_util::parse_ascii_uint;
+use error::NitfError;
+use super::NitfHeader;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentKind {
+  Image,
+  Graphic,
+  Text,
+  DataExtension,
+  ReservedExtension,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Segment {
+  pub kind: SegmentKind,
+  pub subheader_offset: u64,
+  pub subheader_len: u64,
+  pub data_offset: u64,
+  pub data_len: u64,
+}
+
+/// Lays out one group of segments (all images, all graphics, ...) back to
+/// back starting at `start`. Returns the segments along with the offset
+/// just past the last one, so the next group can pick up from there.
+fn offset_table(
+  start: u64,
+  kind: SegmentKind,
+  sh_lens: &[&[u8]],
+  data_lens: &[&[u8]],
+) -> Result<(Vec<Segment>, u64), NitfError> {
+  let mut offset = start;
+  let mut segments = Vec::with_capacity(sh_lens.len());
+
+  for (sh, data) in sh_lens.iter().zip(data_lens.iter()) {
+    let subheader_len = parse_ascii_uint(sh)?;
+    let data_len = parse_ascii_uint(data)?;
+    let subheader_offset = offset;
+    let data_offset = subheader_offset + subheader_len;
+
+    segments.push(Segment {
+      kind: kind,
+      subheader_offset: subheader_offset,
+      subheader_len: subheader_len,
+      data_offset: data_offset,
+      data_len: data_len,
+    });
+
+    offset = data_offset + data_len;
+  }
+
+  Ok((segments, offset))
+}
+
+/// One `(kind, subheader-lengths, data-lengths)` group fed to `offset_table`.
+type SegmentLengthGroup<'a> = (SegmentKind, &'a Vec<&'a [u8]>, &'a Vec<&'a [u8]>);
+
+/// Builds the full segment offset table for every image, graphic, text,
+/// DES and RES segment described in `header`.
+pub fn segments(header: &NitfHeader) -> Result<Vec<Segment>, NitfError> {
+  let mut offset = parse_ascii_uint(header.hl)?;
+  let mut all = Vec::new();
+
+  let groups: [SegmentLengthGroup; 5] = [
+    (SegmentKind::Image, &header.lish, &header.li),
+    (SegmentKind::Graphic, &header.lssh, &header.ls),
+    (SegmentKind::Text, &header.ltsh, &header.lt),
+    (SegmentKind::DataExtension, &header.ldsh, &header.ld),
+    (SegmentKind::ReservedExtension, &header.lrsh, &header.lr),
+  ];
+
+  for &(kind, sh_lens, data_lens) in groups.iter() {
+    let (mut group, next_offset) = offset_table(offset, kind, sh_lens, data_lens)?;
+    offset = next_offset;
+    all.append(&mut group);
+  }
+
+  Ok(all)
+}